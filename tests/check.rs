@@ -0,0 +1,78 @@
+use std::process::Command;
+
+// Runs `simpleblog check` against a site with a missing markdown file and an impossible date,
+// expecting both problems reported and a failing exit code. The site has no templates, which are only warnings
+#[test]
+fn check_fails_on_a_broken_site() {
+    let site_dir =
+        std::env::temp_dir().join(format!("simpleblog-check-cli-{}", std::process::id()));
+    std::fs::create_dir_all(site_dir.join("articles")).unwrap();
+    std::fs::write(
+        site_dir.join("articles.yml"),
+        "- title: Missing\n  article_id: Missing\n  description: d\n  date: \"2024-02-31\"\n",
+    )
+    .unwrap();
+    let config_path = site_dir.join("site_config.yml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "port: 127.0.0.1:3000
+file_path: {}/
+site_title: Test
+site_description: Test
+site_link: http://localhost:3000
+admin_username: admin
+admin_password: admin
+",
+            site_dir.display()
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_simpleblog"))
+        .arg("check")
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&site_dir).unwrap();
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert_eq!(output.status.code(), Some(1), "{}", stdout);
+    assert!(stdout.contains("\"Missing\" is listed in articles.yml but has no markdown file"));
+    assert!(stdout.contains("\"Missing\" has an invalid date"));
+    assert!(stdout.contains("2 problem(s) found"));
+    assert!(stdout.contains("Warning: template"));
+    assert!(!stdout.contains("Error: template"));
+}
+
+// Runs `simpleblog check` against the sample site, which should pass
+#[test]
+fn check_passes_on_the_sample_site() {
+    let site_dir = std::env::temp_dir().join(format!("simpleblog-check-ok-{}", std::process::id()));
+    std::fs::create_dir_all(&site_dir).unwrap();
+    let config_path = site_dir.join("site_config.yml");
+    std::fs::write(
+        &config_path,
+        format!(
+            "port: 127.0.0.1:3000
+file_path: {}/simpleblog/
+site_title: Test
+site_description: Test
+site_link: http://localhost:3000
+admin_username: admin
+admin_password: admin
+",
+            env!("CARGO_MANIFEST_DIR")
+        ),
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_simpleblog"))
+        .arg("check")
+        .arg(&config_path)
+        .output()
+        .unwrap();
+    std::fs::remove_dir_all(&site_dir).unwrap();
+
+    assert_eq!(output.status.code(), Some(0));
+}